@@ -0,0 +1,5 @@
+#![feature(asm)]
+
+extern crate rand;
+
+pub mod multiply_shift;
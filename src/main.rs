@@ -1,4 +1,3 @@
-#![feature(asm)]
 #![feature(test)]
 #![allow(unused_imports, dead_code)]
 
@@ -12,37 +11,157 @@ extern crate regex;
 extern crate rand;
 extern crate ahash as _ahash;
 extern crate rustc_hash;
+extern crate hash_rs;
 
-mod multiply_shift;
+mod quality;
+mod samples;
 
 use std::process::{Stdio, Command};
+use std::env;
+use std::process;
 use std::io::Result as IoResult;
 use std::io::stdout;
 use std::fs::File;
-use regex::Regex;
+use regex::{Captures, Regex};
 
 use std::io::prelude::*;
 use std::collections::HashMap;
+use hash_rs::multiply_shift;
 
 #[cfg(not(test))]
 fn main() {
-    do_it().unwrap();
+    let opts = Options::from_args();
+    if opts.compare_baseline_seed {
+        quality::seed_report().unwrap();
+    } else if let Some(ref path) = opts.dump_samples {
+        samples::dump_samples(path).unwrap();
+    } else {
+        do_it(&opts).unwrap();
+    }
+}
+
+// The bench modules and bench classes defined at the bottom of this file;
+// keep these in sync with them.
+const HASHERS: &'static [&'static str] = &["sip13", "sip24", "fx", "ahash", "xx", "fnv", "horner", "btree"];
+const HASH_CLASSES: &'static [&'static str] = &["bytes", "bytesaligned", "bytesmisaligned",
+                                                "mapcountsparse", "mapcountdense",
+                                                "arraykey16", "arraykey20", "arraykey32"];
+const TREE_CLASSES: &'static [&'static str] = &["mapcountsparse", "mapcountdense"];
+
+fn classes_of(hasher: &str) -> &'static [&'static str] {
+    if hasher == "btree" { TREE_CLASSES } else { HASH_CLASSES }
+}
+
+#[derive(Default)]
+struct Options {
+    /// Time the hashers by hand and write every sample here instead of
+    /// running the benchmarks, if set.
+    dump_samples: Option<String>,
+    /// Run the seed-variance quality report instead of the benchmarks.
+    compare_baseline_seed: bool,
+    /// Only keep results from these hashers (the bench module names), if set.
+    hashers: Option<Vec<String>>,
+    /// Only keep results from these bench classes, if set.
+    classes: Option<Vec<String>>,
+}
+
+impl Options {
+    fn from_args() -> Options {
+        let mut opts = Options::default();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match &*arg {
+                "--dump-samples" => {
+                    let path = args.next().unwrap_or_else(|| {
+                        panic!("--dump-samples expects a file name")
+                    });
+                    opts.dump_samples = Some(path);
+                }
+                "--compare-baseline-seed" => opts.compare_baseline_seed = true,
+                "--hashers" | "--classes" => {
+                    let list = args.next().unwrap_or_else(|| {
+                        panic!("{} expects a comma-separated list", arg)
+                    });
+                    let list = Some(list.split(",").map(String::from).collect());
+                    if arg == "--hashers" { opts.hashers = list } else { opts.classes = list }
+                }
+                _ => panic!("unrecognized argument: {}", arg),
+            }
+        }
+        opts
+    }
+
+    fn keeps(&self, hasher: &str, bench_class: &str) -> bool {
+        fn allows(filter: &Option<Vec<String>>, name: &str) -> bool {
+            filter.as_ref().map_or(true, |names| names.iter().any(|n| n == name))
+        }
+        allows(&self.hashers, hasher) && allows(&self.classes, bench_class)
+    }
+
+    /// Whether any known bench passes the filters, so that a typo can be
+    /// reported before sitting through a whole `cargo bench` run.
+    fn matches_any_bench(&self) -> bool {
+        HASHERS.iter().any(|&hasher| {
+            classes_of(hasher).iter().any(|&bench_class| self.keeps(hasher, bench_class))
+        })
+    }
+
+    /// A libtest filter that narrows `cargo bench` down to (a superset of)
+    /// the benches these filters keep. libtest only takes a single substring,
+    /// so this only narrows by a hasher or class if exactly one was given.
+    fn libtest_filter(&self) -> Option<String> {
+        fn single(filter: &Option<Vec<String>>) -> Option<&str> {
+            match *filter {
+                Some(ref names) if names.len() == 1 => Some(&names[0]),
+                _ => None,
+            }
+        }
+        match (single(&self.hashers), single(&self.classes)) {
+            (Some(hasher), Some(bench_class)) => Some(format!("{}::{}_", hasher, bench_class)),
+            (Some(hasher), None) => Some(format!("{}::", hasher)),
+            (None, Some(bench_class)) => Some(format!("::{}_", bench_class)),
+            (None, None) => None,
+        }
+    }
+
+    fn describe_filter(&self) -> String {
+        let mut desc = vec![];
+        if let Some(ref hashers) = self.hashers {
+            desc.push(format!("--hashers {}", hashers.join(",")));
+        }
+        if let Some(ref classes) = self.classes {
+            desc.push(format!("--classes {}", classes.join(",")));
+        }
+        if desc.is_empty() { String::from("(none)") } else { desc.join(" ") }
+    }
 }
 
 struct DataPoint {
     magnitude: u64,
-    average: u64,
-    variance: u64,
+    // libtest reports fractional nanoseconds for fast benches
+    average: f64,
+    variance: f64,
     throughput: u64,
 }
 
-fn do_it() -> IoResult<()> {
-    let child = Command::new("cargo")
-                         .arg("bench")
-                         .stdout(Stdio::piped())
-                         .spawn()
-                         .unwrap_or_else(|e| { panic!("failed to execute process: {}", e) });
-    let mut out = child.stdout.unwrap();
+/// Bench class -> hasher -> one point per size, in the order they were run.
+type BenchData = HashMap<String, HashMap<String, Vec<DataPoint>>>;
+
+fn do_it(opts: &Options) -> IoResult<()> {
+    if !opts.matches_any_bench() {
+        eprintln!("no benchmarks matched filter {}", opts.describe_filter());
+        process::exit(1);
+    }
+
+    let mut command = Command::new("cargo");
+    command.arg("bench");
+    if let Some(filter) = opts.libtest_filter() {
+        command.arg("--").arg(filter);
+    }
+    let mut child = command.stdout(Stdio::piped())
+                           .spawn()
+                           .unwrap_or_else(|e| { panic!("failed to execute process: {}", e) });
+    let mut out = child.stdout.take().unwrap();
     let mut read_buf = [0u8; 64];
     let mut out_buf: Vec<u8> = Vec::new();
     while let Ok(size) = out.read(&mut read_buf) {
@@ -53,35 +172,42 @@ fn do_it() -> IoResult<()> {
         out_buf.extend(&read_buf[..size]);
     }
 
-    let re = Regex::new(r#"test (.*)::(.*)_(\d*) .*bench:\s*(.*) ns/iter \(\+/- (.*)\) = (\d*) MB/s.*"#).unwrap();
+    let status = try!(child.wait());
+    if !status.success() {
+        eprintln!("cargo bench failed: {}", status);
+        process::exit(1);
+    }
 
     println!("Output:");
 
-    let mut data = HashMap::new();
-
-    for cap in re.captures_iter(&String::from_utf8(out_buf).unwrap()) {
+    let output = String::from_utf8(out_buf).unwrap();
+    for cap in matching_benches(&output, opts) {
         println!("{}", cap.get(0).unwrap().as_str());
-        let hasher = String::from(cap.get(1).unwrap().as_str());
-        let bench_class = String::from(cap.get(2).unwrap().as_str());
+    }
 
-        data.entry(bench_class)
-            .or_insert(HashMap::new())
-            .entry(hasher)
-            .or_insert(vec![])
-            .push(DataPoint {
-                magnitude:  cap.get(3).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
-                average:    cap.get(4).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
-                variance:   cap.get(5).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
-                throughput: cap.get(6).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
-            });
+    let data = parse_bench_output(&output, opts);
+    if data.is_empty() {
+        eprintln!("no benchmarks matched filter {}", opts.describe_filter());
+        process::exit(1);
     }
 
+    for (file_name, contents) in generate_csvs(&data) {
+        let mut file = try!(File::create(&file_name));
+        try!(file.write_all(contents.as_bytes()));
+    }
 
+    Ok(())
+}
 
+/// Transposes `data` into a `<class>-time.csv` and a `<class>-throughput.csv`
+/// per bench class, with one row per size and one column per hasher.
+/// Returns each file's name and contents.
+fn generate_csvs(data: &BenchData) -> Vec<(String, String)> {
+    let mut csvs = vec![];
 
-    for (bench_class, hashers) in &data {
-        let mut time_data = try!(File::create(&format!("{}-time.csv", bench_class)));
-        let mut tput_data = try!(File::create(&format!("{}-throughput.csv", bench_class)));
+    for (bench_class, hashers) in data {
+        let mut time_data: Vec<u8> = vec![];
+        let mut tput_data: Vec<u8> = vec![];
 
         write!(&mut time_data, "bytes").unwrap();
         write!(&mut tput_data, "bytes").unwrap();
@@ -110,12 +236,199 @@ fn do_it() -> IoResult<()> {
             }
             write!(&mut time_data, "\n").unwrap(); write!(&mut tput_data, "\n").unwrap();
         }
+
+        csvs.push((format!("{}-time.csv", bench_class), String::from_utf8(time_data).unwrap()));
+        csvs.push((format!("{}-throughput.csv", bench_class), String::from_utf8(tput_data).unwrap()));
     }
 
-    Ok(())
+    csvs
+}
+
+/// Finds the bench result lines of a `cargo bench` run that pass `opts`'s filters.
+fn matching_benches<'a>(output: &'a str, opts: &Options) -> Vec<Captures<'a>> {
+    let re = Regex::new(r#"test (.*)::(.*)_(\d*) .*bench:\s*(.*) ns/iter \(\+/- (.*)\) = (\d*) MB/s.*"#).unwrap();
+
+    re.captures_iter(output)
+      .filter(|cap| opts.keeps(cap.get(1).unwrap().as_str(), cap.get(2).unwrap().as_str()))
+      .collect()
 }
 
+/// Collects the results of a `cargo bench` run that pass `opts`'s filters.
+fn parse_bench_output(output: &str, opts: &Options) -> BenchData {
+    let mut data = HashMap::new();
 
+    for cap in matching_benches(output, opts) {
+        let hasher = String::from(cap.get(1).unwrap().as_str());
+        let bench_class = String::from(cap.get(2).unwrap().as_str());
+
+        data.entry(bench_class)
+            .or_insert(HashMap::new())
+            .entry(hasher)
+            .or_insert(vec![])
+            .push(DataPoint {
+                magnitude:  cap.get(3).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
+                average:    cap.get(4).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
+                variance:   cap.get(5).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
+                throughput: cap.get(6).unwrap().as_str().split(",").collect::<String>().parse().unwrap(),
+            });
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use super::{classes_of, generate_csvs, parse_bench_output, Options, HASHERS, HASH_CLASSES};
+
+    const FX_OUTPUT: &'static str = "\
+test fx::bytes_000000001          ... bench:           1 ns/iter (+/- 0) = 1000 MB/s
+test fx::bytes_000000002          ... bench:           1 ns/iter (+/- 0) = 2000 MB/s
+";
+
+    #[test]
+    fn unfiltered_output_is_kept() {
+        let data = parse_bench_output(FX_OUTPUT, &Options::default());
+        assert_eq!(data["bytes"]["fx"].len(), 2);
+    }
+
+    #[test]
+    fn fully_filtered_output_is_empty() {
+        let opts = Options {
+            hashers: Some(vec![String::from("sip13")]),
+            ..Options::default()
+        };
+        assert!(parse_bench_output(FX_OUTPUT, &opts).is_empty());
+        assert_eq!(opts.describe_filter(), "--hashers sip13");
+    }
+
+    #[test]
+    fn filters_narrow_cargo_bench_when_they_can() {
+        let list = |names: &[&str]| Some(names.iter().map(|&n| String::from(n)).collect());
+        let filter = |hashers, classes| Options { hashers: hashers, classes: classes, ..Options::default() }
+            .libtest_filter();
+
+        assert_eq!(filter(None, None), None);
+        assert_eq!(filter(list(&["fx"]), None), Some(String::from("fx::")));
+        assert_eq!(filter(None, list(&["bytes"])), Some(String::from("::bytes_")));
+        assert_eq!(filter(list(&["fx"]), list(&["bytes"])), Some(String::from("fx::bytes_")));
+        assert_eq!(filter(list(&["fx", "xx"]), list(&["bytes"])), Some(String::from("::bytes_")));
+        assert_eq!(filter(list(&["fx", "xx"]), list(&["bytes", "mapcountdense"])), None);
+    }
+
+    #[test]
+    fn filters_are_checked_against_known_benches() {
+        assert!(Options::default().matches_any_bench());
+
+        let typo = Options { hashers: Some(vec![String::from("sip31")]), ..Options::default() };
+        assert!(!typo.matches_any_bench());
+
+        // btree only runs the map benches
+        let disjoint = Options {
+            hashers: Some(vec![String::from("btree")]),
+            classes: Some(vec![String::from("bytes")]),
+            ..Options::default()
+        };
+        assert!(!disjoint.matches_any_bench());
+    }
+
+    // The number of bytes each unit of a bench's size parameter stands for.
+    fn bytes_per_unit(bench_class: &str) -> f64 {
+        match bench_class {
+            "mapcountsparse" | "mapcountdense" => 1000.0,
+            "arraykey16" => 16.0,
+            "arraykey20" => 20.0,
+            "arraykey32" => 32.0,
+            _ => 1.0,
+        }
+    }
+
+    // The size parameters each bench class is run with.
+    fn sizes_of(bench_class: &str) -> Vec<u64> {
+        match bench_class {
+            "bytes" | "mapcountsparse" | "mapcountdense" => {
+                vec![1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048]
+            }
+            "bytesaligned" | "bytesmisaligned" => vec![64, 128, 512, 2048],
+            "arraykey16" | "arraykey20" | "arraykey32" => vec![1, 8, 64, 512, 4096],
+            _ => panic!("no sizes known for bench class {}", bench_class),
+        }
+    }
+
+    fn parse_csv(csv: &str) -> (Vec<String>, Vec<Vec<f64>>) {
+        let mut lines = csv.lines();
+        let header = lines.next().unwrap().split(",").map(String::from).collect();
+        let rows = lines.map(|line| {
+            line.split(",").map(|field| field.parse().unwrap()).collect()
+        }).collect();
+        (header, rows)
+    }
+
+    #[test]
+    fn captured_run_produces_sane_csvs() {
+        let output = include_str!("../fixtures/cargo_bench.txt");
+        let data = parse_bench_output(output, &Options::default());
+        let csvs: HashMap<_, _> = generate_csvs(&data).into_iter().collect();
+
+        // "hasher::class_size" -> ns/iter, read straight off the bench lines
+        // without the regex so that the time cells can be checked against it.
+        let mut ns_per_iter = HashMap::new();
+        for line in output.lines().filter(|line| line.contains(" bench: ")) {
+            let words: Vec<_> = line.split_whitespace().collect();
+            let ns = words[words.iter().position(|&w| w == "ns/iter").unwrap() - 1];
+            ns_per_iter.insert(words[1], ns.replace(",", "").parse::<f64>().unwrap());
+        }
+
+        // class -> the hashers that run it, according to HASHERS and friends
+        let mut expected: HashMap<&str, Vec<&str>> = HashMap::new();
+        for &hasher in HASHERS {
+            for &bench_class in classes_of(hasher) {
+                expected.entry(bench_class).or_insert(vec![]).push(hasher);
+            }
+        }
+        assert_eq!(expected.len(), HASH_CLASSES.len());
+        assert_eq!(csvs.len(), expected.len() * 2);
+
+        for (&bench_class, hashers) in &mut expected {
+            hashers.sort();
+            let sizes = sizes_of(bench_class);
+
+            let (time_header, times) = parse_csv(&csvs[&format!("{}-time.csv", bench_class)]);
+            let (tput_header, tputs) = parse_csv(&csvs[&format!("{}-throughput.csv", bench_class)]);
+
+            assert_eq!(time_header, tput_header);
+            assert_eq!(time_header[0], "bytes");
+            let mut columns: Vec<_> = time_header[1..].iter().map(|h| &**h).collect();
+            columns.sort();
+            assert_eq!(&columns, hashers);
+
+            assert_eq!(times.len(), sizes.len());
+            assert_eq!(tputs.len(), sizes.len());
+            for ((time, tput), &size) in times.iter().zip(&tputs).zip(&sizes) {
+                for (hasher, &ns) in time_header[1..].iter().zip(&time[1..]) {
+                    let name = format!("{}::{}_{:09}", hasher, bench_class, size);
+                    assert_eq!(ns, ns_per_iter[&*name], "{}", name);
+                }
+
+                assert_eq!(time.len(), time_header.len());
+                assert_eq!(tput.len(), time_header.len());
+                assert_eq!(time[0], size as f64);
+                assert_eq!(tput[0], size as f64);
+
+                // libtest derives MB/s from the bytes processed and the
+                // (truncated) time per iteration, so the two columns of the
+                // same hasher have to agree with each other.
+                let bytes = size as f64 * bytes_per_unit(bench_class);
+                for (&ns, &mb_s) in time[1..].iter().zip(&tput[1..]) {
+                    assert!(ns > 0.0, "{}: {:?}", bench_class, time);
+                    let implied = bytes * 1000.0 / ns.max(1.0);
+                    assert!(mb_s >= implied / 2.0 && mb_s <= implied * 2.0,
+                            "{} size {}: {} ns/iter but {} MB/s", bench_class, size, ns, mb_s);
+                }
+            }
+        }
+    }
+}
 
 macro_rules! hash_benches {
     ($Impl: ty) => {
@@ -130,7 +443,9 @@ macro_rules! hash_benches {
         use _fnv::FnvHasher as Fnv;
         use std::hash::Hasher;
         use rustc_hash::FxHasher;
-        use std::hash::{BuildHasherDefault, BuildHasher};
+        use std::hash::{BuildHasherDefault, BuildHasher, Hash};
+        use std::mem;
+        use std::slice;
         use multiply_shift::HornerHasher;
 
         use std::collections::HashMap;
@@ -154,6 +469,28 @@ macro_rules! hash_benches {
             });
         }
 
+        fn hasher_bench_offset<H>(b: B, len: usize, offset: usize)
+        where H: Hasher + Default
+        {
+            let hash_state = BuildHasherDefault::<H>::default();
+            // back the bytes with u64s so that offset 0 is 8-byte aligned
+            let mut words = vec![0u64; (len + offset + 7) / 8];
+            let bytes = unsafe {
+                slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, words.len() * 8)
+            };
+            for (byte, val) in bytes.iter_mut().zip((0..100).cycle()) {
+                *byte = val;
+            }
+            let bytes = black_box(&bytes[offset..offset + len]);
+
+            b.bytes = len as u64;
+            b.iter(|| {
+                let mut hasher = hash_state.build_hasher();
+                hasher.write(bytes);
+                hasher.finish()
+            });
+        }
+
         fn map_bench_dense<H>(b: B, len: usize)
         where H: Hasher + Default
         {
@@ -193,6 +530,29 @@ macro_rules! hash_benches {
             });
         }
 
+        // Fixed-size byte arrays (UUIDs, digests, ...) hash as a length
+        // followed by a single `write` of the whole array.
+        fn map_bench_array<H, K>(b: B, num_keys: usize)
+        where H: Hasher + Default, K: Copy + Default + AsMut<[u8]> + Hash + Eq
+        {
+            let mut rng = thread_rng();
+            let keys: Vec<K> = (0..num_keys).map(|_| {
+                let mut key = K::default();
+                rng.fill(key.as_mut());
+                key
+            }).collect();
+            let keys = black_box(keys);
+
+            b.bytes = (num_keys * mem::size_of::<K>()) as u64;
+            b.iter(|| {
+                let mut map = HashMap::with_hasher(BuildHasherDefault::<H>::default());
+                for key in &keys {
+                    *map.entry(*key).or_insert(0) += 1;
+                }
+                map
+            });
+        }
+
         #[bench] fn bytes_000000001(b: B) { hasher_bench::<$Impl>(b, 1) }
         #[bench] fn bytes_000000002(b: B) { hasher_bench::<$Impl>(b, 2) }
         #[bench] fn bytes_000000004(b: B) { hasher_bench::<$Impl>(b, 4) }
@@ -206,6 +566,16 @@ macro_rules! hash_benches {
         #[bench] fn bytes_000001024(b: B) { hasher_bench::<$Impl>(b, 1024) }
         #[bench] fn bytes_000002048(b: B) { hasher_bench::<$Impl>(b, 2048) }
 
+        #[bench] fn bytesaligned_000000064(b: B) { hasher_bench_offset::<$Impl>(b, 64, 0) }
+        #[bench] fn bytesaligned_000000128(b: B) { hasher_bench_offset::<$Impl>(b, 128, 0) }
+        #[bench] fn bytesaligned_000000512(b: B) { hasher_bench_offset::<$Impl>(b, 512, 0) }
+        #[bench] fn bytesaligned_000002048(b: B) { hasher_bench_offset::<$Impl>(b, 2048, 0) }
+
+        #[bench] fn bytesmisaligned_000000064(b: B) { hasher_bench_offset::<$Impl>(b, 64, 1) }
+        #[bench] fn bytesmisaligned_000000128(b: B) { hasher_bench_offset::<$Impl>(b, 128, 1) }
+        #[bench] fn bytesmisaligned_000000512(b: B) { hasher_bench_offset::<$Impl>(b, 512, 1) }
+        #[bench] fn bytesmisaligned_000002048(b: B) { hasher_bench_offset::<$Impl>(b, 2048, 1) }
+
         #[bench] fn mapcountsparse_000000001(b: B) { map_bench_sparse::<$Impl>(b, 1) }
         #[bench] fn mapcountsparse_000000002(b: B) { map_bench_sparse::<$Impl>(b, 2) }
         #[bench] fn mapcountsparse_000000004(b: B) { map_bench_sparse::<$Impl>(b, 4) }
@@ -231,6 +601,24 @@ macro_rules! hash_benches {
         #[bench] fn mapcountdense_000000512(b: B) { map_bench_dense::<$Impl>(b, 512) }
         #[bench] fn mapcountdense_000001024(b: B) { map_bench_dense::<$Impl>(b, 1024) }
         #[bench] fn mapcountdense_000002048(b: B) { map_bench_dense::<$Impl>(b, 2048) }
+
+        #[bench] fn arraykey16_000000001(b: B) { map_bench_array::<$Impl, [u8; 16]>(b, 1) }
+        #[bench] fn arraykey16_000000008(b: B) { map_bench_array::<$Impl, [u8; 16]>(b, 8) }
+        #[bench] fn arraykey16_000000064(b: B) { map_bench_array::<$Impl, [u8; 16]>(b, 64) }
+        #[bench] fn arraykey16_000000512(b: B) { map_bench_array::<$Impl, [u8; 16]>(b, 512) }
+        #[bench] fn arraykey16_000004096(b: B) { map_bench_array::<$Impl, [u8; 16]>(b, 4096) }
+
+        #[bench] fn arraykey20_000000001(b: B) { map_bench_array::<$Impl, [u8; 20]>(b, 1) }
+        #[bench] fn arraykey20_000000008(b: B) { map_bench_array::<$Impl, [u8; 20]>(b, 8) }
+        #[bench] fn arraykey20_000000064(b: B) { map_bench_array::<$Impl, [u8; 20]>(b, 64) }
+        #[bench] fn arraykey20_000000512(b: B) { map_bench_array::<$Impl, [u8; 20]>(b, 512) }
+        #[bench] fn arraykey20_000004096(b: B) { map_bench_array::<$Impl, [u8; 20]>(b, 4096) }
+
+        #[bench] fn arraykey32_000000001(b: B) { map_bench_array::<$Impl, [u8; 32]>(b, 1) }
+        #[bench] fn arraykey32_000000008(b: B) { map_bench_array::<$Impl, [u8; 32]>(b, 8) }
+        #[bench] fn arraykey32_000000064(b: B) { map_bench_array::<$Impl, [u8; 32]>(b, 64) }
+        #[bench] fn arraykey32_000000512(b: B) { map_bench_array::<$Impl, [u8; 32]>(b, 512) }
+        #[bench] fn arraykey32_000004096(b: B) { map_bench_array::<$Impl, [u8; 32]>(b, 4096) }
    }
 }
 
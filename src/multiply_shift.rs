@@ -14,8 +14,10 @@
 use std::ptr::copy_nonoverlapping;
 //#[stable(feature = "rust1", since = "1.0.0")]
 //pub use intrinsics::copy_nonoverlapping;
-use std::hash::Hasher;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::collections::{HashMap, HashSet};
 use std::cmp::min;
+use rand::{Rng, thread_rng};
 
 // This is called a "Horner" hasher because the iterated
 // multiply-shift operation resembles Horner's method for evaluating
@@ -53,10 +55,154 @@ impl Default for HornerHasher {
     }
 }
 
+impl HornerHasher {
+    /// Creates a hasher keyed with the 128-bit number `h1:h0`. The low bit of
+    /// `h0` is forced on, since it must be odd.
+    pub fn with_keys(h0: u64, h1: u64) -> HornerHasher {
+        HornerHasher {h0: h0 | 1,
+                      h1: h1,
+                      result: [0,0,0,0],
+                      accum: [0,0,0,0],
+                      count: 0}
+    }
+}
+
+/// A `HornerHasher` whose result is mixed so that every bit of it is
+/// usable, as std's `HashMap` needs: it picks buckets with the low bits
+/// and tags entries with the top ones. For keys of up to 8 bytes the raw
+/// result is just the key plus a constant, so neither end is usable as is.
+pub struct HornerMapHasher {
+    inner: HornerHasher,
+    // A randomly-chosen odd number to mix the result with.
+    mix: u64,
+}
+
+impl Hasher for HornerMapHasher {
+    fn finish(&self) -> u64 {
+        // Fold the 128-bit product so that every output bit depends on
+        // every bit of the raw result, then run murmur3's finalizer over it:
+        // a single multiply still maps keys that differ in a few bytes onto
+        // a lattice.
+        let product = self.inner.finish() as u128 * self.mix as u128;
+        let mut h = product as u64 ^ (product >> 64) as u64;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51afd7ed558ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
+        h ^ (h >> 33)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes)
+    }
+}
+
+/// A `BuildHasher` that produces `HornerMapHasher`s, keyed with random
+/// numbers chosen when it is created.
+#[derive(Clone, Copy)]
+pub struct HornerBuildHasher {
+    h0: u64,
+    h1: u64,
+    mix: u64,
+}
+
+impl HornerBuildHasher {
+    pub fn new() -> HornerBuildHasher {
+        let mut rng = thread_rng();
+        HornerBuildHasher { h0: rng.gen(), h1: rng.gen(), mix: rng.gen::<u64>() | 1 }
+    }
+}
+
+impl Default for HornerBuildHasher {
+    fn default() -> HornerBuildHasher {
+        HornerBuildHasher::new()
+    }
+}
+
+impl BuildHasher for HornerBuildHasher {
+    type Hasher = HornerMapHasher;
+
+    fn build_hasher(&self) -> HornerMapHasher {
+        HornerMapHasher { inner: HornerHasher::with_keys(self.h0, self.h1), mix: self.mix }
+    }
+}
+
+/// A `HashMap` using `HornerHasher` to hash its keys.
+pub type HornerMap<K, V> = HashMap<K, V, HornerBuildHasher>;
+
+/// A `HashSet` using `HornerHasher` to hash its values.
+pub type HornerSet<T> = HashSet<T, HornerBuildHasher>;
+
+/// Hashes `bytes` in one shot with a default-keyed `HornerHasher`.
+///
+/// ```
+/// use hash_rs::multiply_shift::horner_hash;
+/// assert_eq!(horner_hash(b"horner"), horner_hash(b"horner"));
+/// ```
+///
+/// Discarding the hash is an error under `deny(unused_must_use)`:
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// hash_rs::multiply_shift::horner_hash(b"horner");
+/// ```
+#[must_use = "the hash value is the whole point"]
+pub fn horner_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = HornerHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// Hashes every element of `data`, as `Hash::hash_slice` would, with a
+/// default-keyed `HornerHasher`.
+///
+/// ```
+/// use hash_rs::multiply_shift::{hash_slice, horner_hash};
+/// assert_eq!(hash_slice(b"horner"), horner_hash(b"horner"));
+/// ```
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// hash_rs::multiply_shift::hash_slice(&[1u64, 2, 3]);
+/// ```
+#[must_use = "the hash value is the whole point"]
+pub fn hash_slice<T: Hash>(data: &[T]) -> u64 {
+    let mut hasher = HornerHasher::default();
+    T::hash_slice(data, &mut hasher);
+    hasher.finish()
+}
+
+/// Creates an empty `HornerMap`.
+///
+/// ```
+/// use hash_rs::multiply_shift::horner_map;
+///
+/// let mut map = horner_map();
+/// map.insert("horner", 1);
+/// map.insert("sip", 2);
+/// assert_eq!(map.get("horner"), Some(&1));
+/// assert_eq!(map.get("fnv"), None);
+/// ```
+pub fn horner_map<K, V>() -> HornerMap<K, V> {
+    HornerMap::default()
+}
+
+/// Creates an empty `HornerSet`.
+///
+/// ```
+/// use hash_rs::multiply_shift::horner_set;
+///
+/// let mut set = horner_set();
+/// set.insert("horner");
+/// assert!(set.contains("horner"));
+/// assert!(!set.contains("sip"));
+/// ```
+pub fn horner_set<T>() -> HornerSet<T> {
+    HornerSet::default()
+}
+
 // multiply two 64-bit words and return the 64 most significant bits
 // of the 128-bit product.
-//
-// TODO: implement and test this on other architectures.
 #[cfg(target_arch = "x86_64")]
 #[inline(always)]
 fn hi64mul(x: u64, y: u64) -> u64 {
@@ -68,6 +214,14 @@ fn hi64mul(x: u64, y: u64) -> u64 {
     hi
 }
 
+// Elsewhere (e.g. aarch64's umulh), the compiler knows how to get the
+// high half of a widening multiply.
+#[cfg(not(target_arch = "x86_64"))]
+#[inline(always)]
+fn hi64mul(x: u64, y: u64) -> u64 {
+    ((x as u128 * y as u128) >> 64) as u64
+}
+
 
 // Multiply two 128-bit numbers and write 64 bits of the product to
 // 'result'. The bits written are those starting from the 64th least
@@ -96,9 +250,29 @@ unsafe fn load_u64(buf: &[u8], i: usize) -> u64 {
     data
 }
 
-impl Hasher for HornerHasher {
+/// Load a full u64 word from an 8-byte aligned address in a byte
+/// stream. Yields the same value as `load_u64`, but avoids the
+/// unaligned access penalty on strict-alignment targets.
+///
+/// Unsafe because: unchecked indexing at i..i+8, and &buf[i] must be
+/// 8-byte aligned
+#[inline]
+unsafe fn load_u64_aligned(buf: &[u8], i: usize) -> u64 {
+    debug_assert!(i + 8 <= buf.len());
+    debug_assert!((buf.as_ptr() as usize).wrapping_add(i) % 8 == 0);
+    *(buf.get_unchecked(i) as *const u8 as *const u64)
+}
 
-    fn finish(&self) -> u64 {
+impl HornerHasher {
+    /// The same as `Hasher::finish`, but warns if the result is unused.
+    ///
+    /// ```compile_fail
+    /// #![deny(unused_must_use)]
+    /// let hasher = hash_rs::multiply_shift::HornerHasher::default();
+    /// hasher.finish();
+    /// ```
+    #[must_use = "the hash value is the whole point"]
+    pub fn finish(&self) -> u64 {
         if self.count <= 8 {
             let mut t1 = self.accum[0];
             mult_hi128(&mut t1, self.count, self.h0, self.h1);
@@ -147,13 +321,45 @@ impl Hasher for HornerHasher {
         return result[0];
     }
 
+    // Hashes bytes[i..] into self.result 32 bytes at a time using `load`,
+    // and returns the index of the first byte that was not hashed. `load`
+    // is only ever called with i..i+8 in bounds.
+    #[inline(always)]
+    fn write_blocks<L>(&mut self, bytes: &[u8], mut i: usize, load: L) -> usize
+    where L: Fn(&[u8], usize) -> u64
+    {
+        while i + 31 < bytes.len() {
+            mult_hi128(&mut self.result[0],
+                       load(bytes, i),
+                       self.h0, self.h1);
+            mult_hi128(&mut self.result[1],
+                       load(bytes, i + 8),
+                       self.h0, self.h1);
+            mult_hi128(&mut self.result[2],
+                       load(bytes, i + 16),
+                       self.h0, self.h1);
+            mult_hi128(&mut self.result[3],
+                       load(bytes, i + 24),
+                       self.h0, self.h1);
+            i += 32;
+        }
+        i
+    }
+}
+
+impl Hasher for HornerHasher {
+
+    fn finish(&self) -> u64 {
+        HornerHasher::finish(self)
+    }
+
     fn write(&mut self, bytes: &[u8]) {
         let mut i = 0;
 
         // Fill up self.accum, as much as possible
         let n: u64 = min(32 - (self.count & 31), bytes.len() as u64);
         unsafe {
-            copy_nonoverlapping(bytes.get_unchecked(i),
+            copy_nonoverlapping(bytes.as_ptr().offset(i as isize),
                                 (&mut self.accum[0] as *mut u64 as *mut u8)
                                 .offset((self.count & 31) as isize),
                                 n as usize);
@@ -184,24 +390,151 @@ impl Hasher for HornerHasher {
 
         // This is the main loop: for each 4 64-byte words we pull
         // from bytes, hash it into self.result.
-        while i + 31 < bytes.len() {
-            mult_hi128(&mut self.result[0],
-                       unsafe {load_u64(bytes, i)},
-                       self.h0, self.h1);
-            mult_hi128(&mut self.result[1],
-                       unsafe {load_u64(bytes, i + 8)},
-                       self.h0, self.h1);
-            mult_hi128(&mut self.result[2],
-                       unsafe {load_u64(bytes, i + 16)},
-                       self.h0, self.h1);
-            mult_hi128(&mut self.result[3],
-                       unsafe {load_u64(bytes, i + 24)},
-                       self.h0, self.h1);
-            i += 32;
+        // Since i only ever advances by whole words, every load is
+        // aligned if the first one is.
+        if (bytes.as_ptr() as usize).wrapping_add(i) % 8 == 0 {
+            i = self.write_blocks(bytes, i, |b, j| unsafe { load_u64_aligned(b, j) });
+        } else {
+            i = self.write_blocks(bytes, i, |b, j| unsafe { load_u64(b, j) });
         }
 
         // Add in the remaining data to self.accum.
         let n = bytes.len() - i;
-        unsafe {copy_nonoverlapping(bytes.get_unchecked(i), &mut self.accum[0] as *mut u64 as *mut u8, n);}
+        unsafe {copy_nonoverlapping(bytes.as_ptr().offset(i as isize), &mut self.accum[0] as *mut u64 as *mut u8, n);}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+    use std::slice;
+    use std::hash::{BuildHasher, Hash};
+    use std::collections::HashSet;
+    use super::{HornerBuildHasher, HornerHasher, HornerMap, HornerSet, horner_hash,
+                hash_slice, horner_map, horner_set};
+
+    #[test]
+    fn one_shot_matches_streaming() {
+        let bytes: Vec<u8> = (0..100).collect();
+        let mut hasher = HornerHasher::default();
+        hasher.write(&bytes);
+        let streamed = hasher.finish();
+
+        assert_eq!(horner_hash(&bytes), streamed);
+        // u8's hash_slice feeds the bytes straight through, without a length.
+        assert_eq!(hash_slice(&bytes), streamed);
+    }
+
+    #[test]
+    fn aligned_and_unaligned_agree() {
+        // Back the bytes with u64s so that offset 0 is 8-byte aligned.
+        let mut words = vec![0u64; 40];
+        let bytes = unsafe {
+            slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, words.len() * 8)
+        };
+        for (byte, val) in bytes.iter_mut().zip((0..251).cycle()) {
+            *byte = val;
+        }
+        let mut shifted_words = vec![0u64; 40];
+        let shifted = unsafe {
+            slice::from_raw_parts_mut(shifted_words.as_mut_ptr() as *mut u8,
+                                      shifted_words.len() * 8)
+        };
+        shifted[1..].copy_from_slice(&bytes[..bytes.len() - 1]);
+
+        for len in 0..300 {
+            let aligned = &bytes[..len];
+            let unaligned = &shifted[1..len + 1];
+            assert_eq!(aligned.as_ptr() as usize % 8, 0);
+            assert!(unaligned.as_ptr() as usize % 8 != 0);
+            assert_eq!(aligned, unaligned);
+            assert_eq!(horner_hash(aligned), horner_hash(unaligned), "len {}", len);
+
+            // Also start the main loop partway into the buffer.
+            let mut a = HornerHasher::default();
+            let mut u = HornerHasher::default();
+            a.write(&aligned[..len / 3]);
+            a.write(&aligned[len / 3..]);
+            u.write(&unaligned[..len / 3]);
+            u.write(&unaligned[len / 3..]);
+            assert_eq!(a.finish(), u.finish(), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn map_and_set_insert_and_lookup() {
+        let mut map: HornerMap<&str, i32> = horner_map();
+        map.insert("horner", 1);
+        map.insert("sip", 2);
+        assert_eq!(map.get("horner"), Some(&1));
+        assert_eq!(map.get("sip"), Some(&2));
+        assert_eq!(map.get("fnv"), None);
+
+        let mut set: HornerSet<Vec<u8>> = horner_set();
+        for len in 0..100 {
+            assert!(set.insert((0..len).collect()));
+        }
+        assert_eq!(set.len(), 100);
+        assert!(set.contains(&(0..40).collect::<Vec<u8>>()));
+        assert!(!set.contains(&vec![1, 2, 3]));
+    }
+
+    // Keys that only differ in a few bits, and not at the ends.
+    fn patterned_keys() -> (Vec<String>, Vec<u64>) {
+        let strings = (0..4096).map(|i| format!("k{:06}", i)).collect();
+        let strides = (0..4096).map(|i| i << 16).collect();
+        (strings, strides)
+    }
+
+    // How many distinct low-12-bit buckets and top-7-bit tags `keys` get.
+    fn spread<K: Hash>(build: &HornerBuildHasher, keys: &[K]) -> (usize, usize) {
+        let mut buckets = HashSet::new();
+        let mut tags = HashSet::new();
+        for key in keys {
+            let mut hasher = build.build_hasher();
+            key.hash(&mut hasher);
+            let hash = hasher.finish();
+            buckets.insert(hash & 0xfff);
+            tags.insert(hash >> 57);
+        }
+        (buckets.len(), tags.len())
+    }
+
+    #[test]
+    fn patterned_keys_spread_over_low_and_high_bits() {
+        let (strings, strides) = patterned_keys();
+        let build = HornerBuildHasher::new();
+
+        // 4096 random hashes would hit about 2589 of 4096 buckets, and
+        // all 128 tags.
+        for &(buckets, tags) in &[spread(&build, &strings), spread(&build, &strides)] {
+            assert!(buckets > 2300, "{} buckets", buckets);
+            assert_eq!(tags, 128);
+        }
+
+        let mut map: HornerMap<&str, usize> = horner_map();
+        let mut set: HornerSet<u64> = horner_set();
+        for (i, (string, &stride)) in strings.iter().zip(&strides).enumerate() {
+            map.insert(string, i);
+            set.insert(stride);
+        }
+        assert_eq!(map.len(), strings.len());
+        assert_eq!(set.len(), strides.len());
+        for (i, (string, stride)) in strings.iter().zip(&strides).enumerate() {
+            assert_eq!(map[&**string], i);
+            assert!(set.contains(stride));
+        }
+    }
+
+    #[test]
+    fn build_hashers_are_randomly_keyed() {
+        let (a, b) = (HornerBuildHasher::new(), HornerBuildHasher::new());
+        let hash = |build: &HornerBuildHasher| {
+            let mut hasher = build.build_hasher();
+            hasher.write(b"horner");
+            hasher.finish()
+        };
+        assert_eq!(hash(&a), hash(&a));
+        assert!(hash(&a) != hash(&b));
     }
 }
@@ -0,0 +1,180 @@
+// Measures how evenly the randomized hashers spread a fixed set of keys over
+// a hash table, and how much that depends on the seed they were keyed with.
+//
+// Every hasher hashes the same NUM_KEYS keys (u64 counters, fed through
+// `write_u64` as a `HashMap<u64, _>` would) into NUM_KEYS buckets, using the top
+// BUCKET_BITS bits of the hash as the bucket index. For each seed we count
+// the collisions (keys landing in an already occupied bucket) and the
+// chi-square statistic of the bucket loads.
+//
+// Under ideal random hashing with a load factor of 1 and m buckets, the
+// number of collisions has a mean of about m/e and a standard deviation of
+// about sqrt(m * e^-1 * (1 - 2e^-1)), and the chi-square statistic has a
+// mean of m - 1 and a standard deviation of sqrt(2(m - 1)). Each hasher
+// gets two verdicts:
+//
+// * seed_sensitive: its collision count varies across seeds more than
+//   SENSITIVITY times the ideal standard deviation. A good universal hash
+//   should give roughly the same numbers for any seed.
+// * poor_distribution: its mean collision count or mean chi-square is more
+//   than POOR_SIGMAS ideal standard deviations above the ideal mean, i.e.
+//   it is consistently worse than random, whatever the seed. Being better
+//   than random (spreading these sequential keys more evenly) is fine.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Result as IoResult;
+use std::io::prelude::*;
+use std::fs::File;
+use std::f64;
+use rand::{Rng, thread_rng};
+use rand::rngs::ThreadRng;
+use _ahash;
+use twox_hash::XxHash;
+use multiply_shift::HornerHasher;
+
+const BUCKET_BITS: u32 = 14;
+const NUM_KEYS: u64 = 1 << BUCKET_BITS;
+const NUM_SEEDS: usize = 16;
+const SENSITIVITY: f64 = 2.0;
+const POOR_SIGMAS: f64 = 6.0;
+
+struct Quality {
+    collisions: u64,
+    chi_square: f64,
+}
+
+struct Stats {
+    min: f64,
+    max: f64,
+    mean: f64,
+    stddev: f64,
+}
+
+impl Stats {
+    fn of<I: Iterator<Item = f64> + Clone>(values: I) -> Stats {
+        let n = values.clone().count() as f64;
+        let mean = values.clone().sum::<f64>() / n;
+        let variance = values.clone().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Stats {
+            min: values.clone().fold(f64::INFINITY, f64::min),
+            max: values.fold(f64::NEG_INFINITY, f64::max),
+            mean: mean,
+            stddev: variance.sqrt(),
+        }
+    }
+}
+
+struct Spread {
+    collisions: Stats,
+    chi_square: Stats,
+}
+
+fn measure<F, H>(mut make_hasher: F) -> Quality
+where F: FnMut() -> H, H: Hasher
+{
+    let num_buckets = 1usize << BUCKET_BITS;
+    let mut buckets = vec![0u64; num_buckets];
+    for key in 0..NUM_KEYS {
+        let mut hasher = make_hasher();
+        hasher.write_u64(key);
+        buckets[(hasher.finish() >> (64 - BUCKET_BITS)) as usize] += 1;
+    }
+
+    let expected = NUM_KEYS as f64 / num_buckets as f64;
+    let mut quality = Quality { collisions: 0, chi_square: 0.0 };
+    for &load in &buckets {
+        quality.collisions += load.saturating_sub(1);
+        let diff = load as f64 - expected;
+        quality.chi_square += diff * diff / expected;
+    }
+    quality
+}
+
+fn spread<F>(rng: &mut ThreadRng, mut measure_seed: F) -> Spread
+where F: FnMut(&mut ThreadRng) -> Quality
+{
+    let samples: Vec<Quality> = (0..NUM_SEEDS).map(|_| measure_seed(rng)).collect();
+    Spread {
+        collisions: Stats::of(samples.iter().map(|q| q.collisions as f64)),
+        chi_square: Stats::of(samples.iter().map(|q| q.chi_square)),
+    }
+}
+
+fn num_buckets() -> f64 {
+    (1u64 << BUCKET_BITS) as f64
+}
+
+/// The mean collision count under ideal random hashing.
+fn ideal_collisions() -> f64 {
+    let m = num_buckets();
+    NUM_KEYS as f64 - m * (1.0 - (-(NUM_KEYS as f64) / m).exp())
+}
+
+/// The standard deviation of the collision count under ideal random hashing.
+fn ideal_stddev() -> f64 {
+    let m = num_buckets();
+    let p = (-(NUM_KEYS as f64) / m).exp();
+    (m * p * (1.0 - (1.0 + NUM_KEYS as f64 / m) * p)).sqrt()
+}
+
+fn is_seed_sensitive(s: &Spread) -> bool {
+    s.collisions.stddev > SENSITIVITY * ideal_stddev()
+}
+
+fn has_poor_distribution(s: &Spread) -> bool {
+    let dof = num_buckets() - 1.0;
+    s.collisions.mean > ideal_collisions() + POOR_SIGMAS * ideal_stddev() ||
+        s.chi_square.mean > dof + POOR_SIGMAS * (2.0 * dof).sqrt()
+}
+
+/// Runs the collision analysis for every randomized hasher under NUM_SEEDS
+/// random seeds, and writes the spread to `quality-seeds.csv`.
+pub fn seed_report() -> IoResult<()> {
+    let mut rng = thread_rng();
+    let results = vec![
+        ("sip13", spread(&mut rng, |_| {
+            let state = RandomState::new();
+            measure(|| state.build_hasher())
+        })),
+        ("ahash", spread(&mut rng, |rng| {
+            let (k0, k1) = (rng.gen(), rng.gen());
+            measure(|| _ahash::AHasher::new_with_keys(k0, k1))
+        })),
+        ("xx", spread(&mut rng, |rng| {
+            let seed = rng.gen();
+            measure(|| XxHash::with_seed(seed))
+        })),
+        ("horner", spread(&mut rng, |rng| {
+            let (h0, h1) = (rng.gen(), rng.gen());
+            measure(|| HornerHasher::with_keys(h0, h1))
+        })),
+    ];
+
+    let mut out = try!(File::create("quality-seeds.csv"));
+    try!(write!(&mut out, "hasher,seeds,\
+                           min_collisions,max_collisions,mean_collisions,stddev_collisions,\
+                           min_chi_square,max_chi_square,mean_chi_square,stddev_chi_square,\
+                           seed_sensitive,poor_distribution\n"));
+
+    println!("{} keys in {} buckets over {} seeds; ideally collisions {:.1} +/- {:.1}, \
+              chi2 {:.1} +/- {:.1}:",
+             NUM_KEYS, num_buckets(), NUM_SEEDS, ideal_collisions(), ideal_stddev(),
+             num_buckets() - 1.0, (2.0 * (num_buckets() - 1.0)).sqrt());
+    for &(hasher, ref s) in &results {
+        let (sensitive, poor) = (is_seed_sensitive(s), has_poor_distribution(s));
+        let (c, x) = (&s.collisions, &s.chi_square);
+        try!(write!(&mut out, "{},{},{},{},{:.1},{:.1},{:.1},{:.1},{:.1},{:.1},{},{}\n",
+                    hasher, NUM_SEEDS,
+                    c.min, c.max, c.mean, c.stddev,
+                    x.min, x.max, x.mean, x.stddev,
+                    sensitive, poor));
+        println!("{:>8}: collisions {:>6}..{:<6} mean {:>8.1} stddev {:>7.1} \
+                  chi2 {:>12.1}..{:<12.1} mean {:>12.1} stddev {:>10.1}{}{}",
+                 hasher, c.min, c.max, c.mean, c.stddev, x.min, x.max, x.mean, x.stddev,
+                 if sensitive { "  <- seed-sensitive" } else { "" },
+                 if poor { "  <- poor distribution" } else { "" });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// Times the `bytes` workload (hashing a byte string with a freshly built
+// hasher, as `hasher_bench` does) by hand, one iteration at a time, and
+// writes out every single timing so that bootstrapping, outlier detection
+// and the like can be done externally. libtest's #[bench] only reports a
+// median and a range, so it can't provide these.
+//
+// Every timing includes the cost of reading the clock. The `timer` rows time
+// an empty iteration, so that this overhead can be estimated and subtracted.
+
+use std::collections::hash_map::DefaultHasher as Sip13;
+#[allow(deprecated)]
+use std::hash::SipHasher as Sip24;
+use std::hash::{BuildHasherDefault, BuildHasher, Hasher};
+use std::io::Result as IoResult;
+use std::io::prelude::*;
+use std::fs::File;
+use std::time::{Duration, Instant};
+use test::black_box;
+use _ahash::AHasher as AHash;
+use twox_hash::XxHash as Xx;
+use _fnv::FnvHasher as Fnv;
+use rustc_hash::FxHasher;
+use multiply_shift::HornerHasher;
+
+const SIZES: &'static [usize] = &[1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024, 2048];
+const WARMUP: usize = 100;
+const SAMPLES: usize = 1000;
+
+fn nanos(elapsed: Duration) -> u64 {
+    elapsed.as_secs() * 1_000_000_000 + elapsed.subsec_nanos() as u64
+}
+
+fn time_hasher<H>(len: usize) -> Vec<u64>
+where H: Hasher + Default
+{
+    let hash_state = BuildHasherDefault::<H>::default();
+    let bytes: Vec<u8> = (0..100).cycle().take(len).collect();
+    let bytes = black_box(bytes);
+
+    let mut samples = Vec::with_capacity(SAMPLES);
+    for i in 0..WARMUP + SAMPLES {
+        let start = Instant::now();
+        let mut hasher = hash_state.build_hasher();
+        hasher.write(&bytes);
+        black_box(hasher.finish());
+        let elapsed = start.elapsed();
+        if i >= WARMUP {
+            samples.push(nanos(elapsed));
+        }
+    }
+    samples
+}
+
+fn time_nothing() -> Vec<u64> {
+    let mut samples = Vec::with_capacity(SAMPLES);
+    for i in 0..WARMUP + SAMPLES {
+        let start = Instant::now();
+        black_box(0u64);
+        let elapsed = start.elapsed();
+        if i >= WARMUP {
+            samples.push(nanos(elapsed));
+        }
+    }
+    samples
+}
+
+/// Writes SAMPLES per-iteration timings for every hasher and size to `path`,
+/// one per row of `class,hasher,bytes,iteration,ns`. See the README.
+pub fn dump_samples(path: &str) -> IoResult<()> {
+    let mut out = try!(File::create(path));
+    try!(write!(&mut out, "class,hasher,bytes,iteration,ns\n"));
+
+    let mut runs = vec![("timer", 0, time_nothing())];
+    for &len in SIZES {
+        runs.push(("sip13", len, time_hasher::<Sip13>(len)));
+        runs.push(("sip24", len, time_hasher::<Sip24>(len)));
+        runs.push(("fx", len, time_hasher::<FxHasher>(len)));
+        runs.push(("ahash", len, time_hasher::<AHash>(len)));
+        runs.push(("xx", len, time_hasher::<Xx>(len)));
+        runs.push(("fnv", len, time_hasher::<Fnv>(len)));
+        runs.push(("horner", len, time_hasher::<HornerHasher>(len)));
+    }
+
+    for &(hasher, len, ref samples) in &runs {
+        for (iteration, ns) in samples.iter().enumerate() {
+            try!(write!(&mut out, "bytes,{},{},{},{}\n", hasher, len, iteration, ns));
+        }
+    }
+
+    Ok(())
+}